@@ -1,15 +1,45 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use clap::{Args, Subcommand};
-use dougu_domain_dropbox::DropboxClient;
+use chrono::{DateTime, Utc};
+use dougu_domain_dropbox::{
+    DropboxAuth, DropboxClient, DropboxEntry, PkceChallenge, SharedLinkSettings, StoredToken,
+};
+use std::io::{self, Write};
+
+/// Environment variable read when `--token` is not given
+pub const TOKEN_ENV_VAR: &str = "DOUGU_DROPBOX_TOKEN";
+
+/// Environment variable read when `--password` is not given for a shared link
+pub const LINK_PASSWORD_ENV_VAR: &str = "DOUGU_DROPBOX_LINK_PASSWORD";
 
 #[derive(Debug, Args)]
 pub struct DropboxArgs {
+    /// Dropbox access token
+    #[arg(long, global = true, env = TOKEN_ENV_VAR, hide_env_values = true)]
+    pub token: Option<String>,
+    
     #[command(subcommand)]
     pub command: DropboxCommands,
 }
 
+impl DropboxArgs {
+    /// Resolve the access token: `--token`, then the environment variable,
+    /// then the token stored by `dougu dropbox auth`
+    pub async fn resolve_token(&self) -> Result<String> {
+        match &self.token {
+            Some(token) => Ok(token.clone()),
+            None => dougu_domain_dropbox::load_access_token().await.map_err(|e| {
+                anyhow!("{}; alternatively pass --token or set {}", e, TOKEN_ENV_VAR)
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum DropboxCommands {
+    /// Authorize dougu to access your Dropbox account
+    Auth(AuthArgs),
+    
     /// File operations for Dropbox
     File(FileArgs),
     
@@ -17,6 +47,13 @@ pub enum DropboxCommands {
     Folder(FolderArgs),
 }
 
+#[derive(Debug, Args)]
+pub struct AuthArgs {
+    /// App key of the Dropbox app to authorize
+    #[arg(long, env = "DOUGU_DROPBOX_APP_KEY")]
+    pub app_key: String,
+}
+
 #[derive(Debug, Args)]
 pub struct FileArgs {
     #[command(subcommand)]
@@ -33,6 +70,9 @@ pub enum FileCommands {
     
     /// Upload file to Dropbox
     Upload(UploadFileArgs),
+    
+    /// Create a shared link for a Dropbox file or folder
+    Share(ShareFileArgs),
 }
 
 #[derive(Debug, Args)]
@@ -62,6 +102,14 @@ pub struct ListFileArgs {
     /// Use long listing format
     #[arg(short, long)]
     pub long: bool,
+    
+    /// List only changes since this cursor from a previous listing
+    #[arg(long, conflicts_with = "path")]
+    pub cursor: Option<String>,
+    
+    /// Print a cursor for the current state of the folder without listing it
+    #[arg(long, conflicts_with = "cursor")]
+    pub latest_cursor: bool,
 }
 
 #[derive(Debug, Args)]
@@ -87,6 +135,28 @@ pub struct UploadFileArgs {
     pub force: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct ShareFileArgs {
+    /// Dropbox path to share
+    pub path: String,
+    
+    /// Require this password to open the link; prefer the environment variable
+    /// to keep it out of shell history
+    #[arg(long, env = LINK_PASSWORD_ENV_VAR, hide_env_values = true)]
+    pub password: Option<String>,
+    
+    /// Expiry time of the link in RFC 3339 format
+    #[arg(long, value_parser = parse_expires)]
+    pub expires: Option<String>,
+}
+
+/// Normalize an RFC 3339 timestamp to the UTC form Dropbox expects
+fn parse_expires(value: &str) -> std::result::Result<String, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .map_err(|e| format!("invalid RFC 3339 timestamp: {}", e))
+}
+
 #[derive(Debug, Args)]
 pub struct CreateFolderArgs {
     /// Dropbox path to create folder at
@@ -107,25 +177,79 @@ pub struct DeleteFolderArgs {
     pub force: bool,
 }
 
+/// Execute the Dropbox auth command
+pub async fn execute_auth(args: &AuthArgs, dry_run: bool) -> Result<()> {
+    if dry_run {
+        let path = StoredToken::default_path()?;
+        println!("Would authorize app {} and save the Dropbox token to {}", args.app_key, path.display());
+        return Ok(());
+    }
+    
+    let auth = DropboxAuth::new(args.app_key.clone());
+    let pkce = PkceChallenge::generate();
+    
+    println!("1. Open this URL and allow access:\n   {}", auth.authorize_url(&pkce));
+    print!("2. Paste the authorization code here: ");
+    io::stdout().flush()?;
+    
+    let mut code = String::new();
+    io::stdin().read_line(&mut code)?;
+    if code.trim().is_empty() {
+        return Err(anyhow!("No authorization code entered"));
+    }
+    
+    let token = auth.exchange_code(&code, &pkce).await?;
+    let path = StoredToken::default_path()?;
+    token.save(&path)?;
+    
+    println!("Saved Dropbox token to {}", path.display());
+    Ok(())
+}
+
 /// Execute the Dropbox file list command
 pub async fn execute_file_list(args: &ListFileArgs, token: &str) -> Result<()> {
     let client = DropboxClient::new(token.to_string());
     let path = args.path.as_deref().unwrap_or("");
     
-    dougu_essentials_logger::log_info(format!("Listing Dropbox files in: {}", path));
+    if args.latest_cursor {
+        println!("{}", client.list_folder_latest_cursor(path).await?);
+        return Ok(());
+    }
     
-    let result = client.list_files(path).await?;
+    let (entries, cursor) = match &args.cursor {
+        Some(cursor) => {
+            dougu_essentials_logger::log_info(format!("Listing Dropbox changes since cursor: {}", cursor));
+            client.list_folder_changes(cursor).await?
+        }
+        None => {
+            dougu_essentials_logger::log_info(format!("Listing Dropbox files in: {}", path));
+            client.list_folder_with_cursor(path).await?
+        }
+    };
     
-    for file in &result.files {
-        println!("{} ({})", file.name, file.size);
+    for entry in entries.iter().filter(|e| args.all || !e.name().starts_with('.')) {
+        match entry {
+            DropboxEntry::File { name, size, server_modified, .. } if args.long => {
+                println!("{:>12}  {}  {}", size, server_modified, name);
+            }
+            DropboxEntry::File { name, .. } => println!("{}", name),
+            DropboxEntry::Folder { name, .. } if args.long => {
+                println!("{:>12}  {:<20}  {}/", "-", "-", name);
+            }
+            DropboxEntry::Folder { name, .. } => println!("{}/", name),
+            DropboxEntry::Deleted { .. } => println!("deleted: {}", entry.path()),
+        }
     }
     
+    // Printed to stderr so the listing itself stays pipeable
+    eprintln!("Cursor: {}", cursor);
+    
     Ok(())
 }
 
 /// Execute the Dropbox file download command
 pub async fn execute_file_download(args: &DownloadFileArgs, token: &str) -> Result<()> {
-    let client = DropboxClient::new(token.to_string());
+    let _client = DropboxClient::new(token.to_string());
     
     dougu_essentials_logger::log_info(format!("Downloading file from Dropbox: {}", args.path));
     
@@ -137,7 +261,7 @@ pub async fn execute_file_download(args: &DownloadFileArgs, token: &str) -> Resu
 
 /// Execute the Dropbox file upload command
 pub async fn execute_file_upload(args: &UploadFileArgs, token: &str) -> Result<()> {
-    let client = DropboxClient::new(token.to_string());
+    let _client = DropboxClient::new(token.to_string());
     
     dougu_essentials_logger::log_info(format!("Uploading file to Dropbox: {}", args.dropbox_path));
     
@@ -147,9 +271,32 @@ pub async fn execute_file_upload(args: &UploadFileArgs, token: &str) -> Result<(
     Ok(())
 }
 
+/// Execute the Dropbox file share command
+pub async fn execute_file_share(args: &ShareFileArgs, token: &str, dry_run: bool) -> Result<()> {
+    if dry_run {
+        println!("Would create a shared link for {}", args.path);
+        return Ok(());
+    }
+    
+    let client = DropboxClient::new(token.to_string());
+    
+    let mut settings = SharedLinkSettings::default();
+    if let Some(password) = &args.password {
+        settings = settings.with_password(password.clone());
+    }
+    if let Some(expires) = &args.expires {
+        settings = settings.with_expires(expires.clone());
+    }
+    
+    let link = client.create_shared_link(&args.path, &settings).await?;
+    println!("{}", link.url);
+    
+    Ok(())
+}
+
 /// Execute the Dropbox folder create command
 pub async fn execute_folder_create(args: &CreateFolderArgs, token: &str) -> Result<()> {
-    let client = DropboxClient::new(token.to_string());
+    let _client = DropboxClient::new(token.to_string());
     
     dougu_essentials_logger::log_info(format!("Creating Dropbox folder: {}", args.path));
     
@@ -161,7 +308,7 @@ pub async fn execute_folder_create(args: &CreateFolderArgs, token: &str) -> Resu
 
 /// Execute the Dropbox folder delete command
 pub async fn execute_folder_delete(args: &DeleteFolderArgs, token: &str) -> Result<()> {
-    let client = DropboxClient::new(token.to_string());
+    let _client = DropboxClient::new(token.to_string());
     
     dougu_essentials_logger::log_info(format!("Deleting Dropbox folder: {}", args.path));
     
@@ -170,3 +317,48 @@ pub async fn execute_folder_delete(args: &DeleteFolderArgs, token: &str) -> Resu
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        dropbox: DropboxArgs,
+    }
+
+    #[test]
+    fn token_flag_is_parsed() {
+        let cli = TestCli::parse_from(["dougu", "file", "list", "--token", "cli-token"]);
+        assert_eq!(cli.dropbox.token.as_deref(), Some("cli-token"));
+    }
+
+    #[test]
+    fn list_cursor_conflicts_with_path() {
+        assert!(TestCli::try_parse_from(["dougu", "file", "list", "/a", "--cursor", "c"]).is_err());
+        assert!(TestCli::try_parse_from(["dougu", "file", "list", "--cursor", "c", "--latest-cursor"]).is_err());
+        assert!(TestCli::try_parse_from(["dougu", "file", "list", "/a", "--latest-cursor"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn dry_run_skips_dropbox_calls() {
+        let args = ShareFileArgs {
+            path: "/a.txt".to_string(),
+            password: None,
+            expires: None,
+        };
+        // Any API call would fail with this token or without network access
+        execute_file_share(&args, "invalid-token", true).await.unwrap();
+
+        let args = AuthArgs { app_key: "app".to_string() };
+        execute_auth(&args, true).await.unwrap();
+    }
+
+    #[test]
+    fn share_expires_is_normalized_to_utc() {
+        assert_eq!(parse_expires("2030-01-01T09:00:00+09:00").unwrap(), "2030-01-01T00:00:00Z");
+        assert!(parse_expires("tomorrow").is_err());
+    }
+}
@@ -0,0 +1,43 @@
+// Kept in its own test binary with a single test: mutating the process
+// environment is only sound while no other thread reads it, and every
+// `TestCli` parse reads the environment through clap.
+
+use clap::Parser;
+use dougu_command_dropbox::{
+    DropboxArgs, DropboxCommands, FileArgs, FileCommands, LINK_PASSWORD_ENV_VAR, TOKEN_ENV_VAR,
+};
+
+#[derive(Debug, Parser)]
+struct TestCli {
+    #[command(flatten)]
+    dropbox: DropboxArgs,
+}
+
+#[test]
+fn options_fall_back_to_environment() {
+    // SAFETY: this is the only test in this binary, so no other thread is running
+    unsafe {
+        std::env::set_var(TOKEN_ENV_VAR, "env-token");
+        std::env::set_var(LINK_PASSWORD_ENV_VAR, "secret");
+    }
+
+    let cli = TestCli::parse_from(["dougu", "file", "list"]);
+    assert_eq!(cli.dropbox.token.as_deref(), Some("env-token"));
+
+    let cli = TestCli::parse_from(["dougu", "file", "list", "--token", "cli-token"]);
+    assert_eq!(cli.dropbox.token.as_deref(), Some("cli-token"));
+
+    let cli = TestCli::parse_from(["dougu", "file", "share", "/a.txt"]);
+    let DropboxCommands::File(FileArgs { command: FileCommands::Share(args) }) = cli.dropbox.command else {
+        panic!("expected file share");
+    };
+    assert_eq!(args.password.as_deref(), Some("secret"));
+
+    // SAFETY: as above
+    unsafe {
+        std::env::remove_var(TOKEN_ENV_VAR);
+        std::env::remove_var(LINK_PASSWORD_ENV_VAR);
+    }
+    let cli = TestCli::parse_from(["dougu", "file", "list"]);
+    assert_eq!(cli.dropbox.token, None);
+}
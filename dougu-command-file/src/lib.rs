@@ -1,5 +1,13 @@
-use anyhow::Result;
-use clap::{Args, Subcommand};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::{ArgAction, Args, Subcommand};
+use glob::Pattern;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use walkdir::WalkDir;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
@@ -9,11 +17,330 @@ pub fn add(left: u64, right: u64) -> u64 {
 mod tests {
     use super::*;
 
+    fn code(err: &anyhow::Error) -> FileErrorCode {
+        err.downcast_ref::<FileError>().unwrap().code.clone()
+    }
+
+    #[test]
+    fn error_codes_keep_their_string_forms() {
+        let codes = [
+            (FileErrorCode::FileNotFound, ERROR_FILE_NOT_FOUND),
+            (FileErrorCode::AccessDenied, ERROR_ACCESS_DENIED),
+            (FileErrorCode::InvalidPath, ERROR_INVALID_PATH),
+            (FileErrorCode::AlreadyExists, ERROR_ALREADY_EXISTS),
+            (FileErrorCode::Other("QUOTA_EXCEEDED".to_string()), "QUOTA_EXCEEDED"),
+        ];
+        for (code, text) in codes {
+            assert_eq!(code.to_string(), text);
+            assert_eq!(serde_json::to_string(&code).unwrap(), format!("\"{}\"", text));
+            assert_eq!(FileErrorCode::from(text), code);
+        }
+
+        let err = file_error(FileErrorCode::AlreadyExists, "Destination 'a' already exists".to_string());
+        assert_eq!(err.to_string(), "ALREADY_EXISTS: Destination 'a' already exists");
+        assert_eq!(code(&err), FileErrorCode::AlreadyExists);
+    }
+
     #[test]
     fn it_works() {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn dry_run_copy_touches_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.txt");
+        let destination = dir.path().join("destination.txt");
+        std::fs::write(&source, "content").unwrap();
+
+        let args = CopyArgs {
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+            force: false,
+            recursive: false,
+            follow_symlinks: true,
+        };
+        let result = execute_copy(&args, true).unwrap();
+
+        assert!(result.success);
+        assert!(result.message.starts_with("Would copy"));
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn copy_respects_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.txt");
+        let destination = dir.path().join("destination.txt");
+        std::fs::write(&source, "new").unwrap();
+        std::fs::write(&destination, "old").unwrap();
+
+        let mut args = CopyArgs {
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+            force: false,
+            recursive: false,
+            follow_symlinks: true,
+        };
+        let err = execute_copy(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "old");
+
+        args.force = true;
+        execute_copy(&args, false).unwrap();
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "new");
+    }
+
+    #[test]
+    fn copy_directory_recursively() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        let destination = dir.path().join("destination");
+        std::fs::create_dir_all(source.join("nested")).unwrap();
+        std::fs::write(source.join("top.txt"), "top").unwrap();
+        std::fs::write(source.join("nested").join("inner.txt"), "inner").unwrap();
+
+        let mut args = CopyArgs {
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+            force: false,
+            recursive: false,
+            follow_symlinks: true,
+        };
+        let err = execute_copy(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::InvalidPath);
+
+        args.recursive = true;
+        let result = execute_copy(&args, false).unwrap();
+        assert_eq!(result.details.as_deref(), Some("2 files copied"));
+        assert_eq!(std::fs::read_to_string(destination.join("top.txt")).unwrap(), "top");
+        assert_eq!(
+            std::fs::read_to_string(destination.join("nested").join("inner.txt")).unwrap(),
+            "inner"
+        );
+
+        let err = execute_copy(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::AlreadyExists);
+    }
+
+    #[test]
+    fn copy_onto_itself_keeps_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "content").unwrap();
+
+        let args = CopyArgs {
+            source: path.display().to_string(),
+            destination: dir.path().join(".").join("a.txt").display().to_string(),
+            force: true,
+            recursive: false,
+            follow_symlinks: true,
+        };
+        let err = execute_copy(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::InvalidPath);
+
+        let args = MoveArgs {
+            source: args.source,
+            destination: args.destination,
+            force: true,
+        };
+        let err = execute_move(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::InvalidPath);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "content");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_refuses_dangling_symlink_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.txt");
+        let link = dir.path().join("dl");
+        std::fs::write(&source, "content").unwrap();
+        std::os::unix::fs::symlink("nowhere.txt", &link).unwrap();
+
+        let args = CopyArgs {
+            source: source.display().to_string(),
+            destination: link.display().to_string(),
+            force: false,
+            recursive: false,
+            follow_symlinks: true,
+        };
+        let err = execute_copy(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::AlreadyExists);
+        assert!(!dir.path().join("nowhere.txt").exists());
+    }
+
+    #[test]
+    fn move_renames_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.txt");
+        let destination = dir.path().join("destination.txt");
+        std::fs::write(&source, "content").unwrap();
+
+        let args = MoveArgs {
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+            force: false,
+        };
+        execute_move(&args, false).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(std::fs::read_to_string(&destination).unwrap(), "content");
+
+        let err = execute_move(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::FileNotFound);
+    }
+
+    #[test]
+    fn list_skips_hidden_files_unless_all() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join(".hidden"), "h").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let mut args = ListArgs {
+            directory: Some(dir.path().display().to_string()),
+            all: false,
+            long: false,
+            pattern: None,
+            json: false,
+            follow_symlinks: true,
+        };
+        let result = execute_list(&args).unwrap();
+        assert_eq!(result.render(false), "a.txt\nb.txt\nsub/");
+
+        args.all = true;
+        let result = execute_list(&args).unwrap();
+        let rendered = result.render(true);
+        assert_eq!(rendered.lines().count(), 4);
+        assert!(rendered.lines().next().unwrap().ends_with(".hidden"));
+    }
+
+    #[test]
+    fn list_filters_by_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("c.rs"), "c").unwrap();
+        std::fs::write(dir.path().join(".d.txt"), "d").unwrap();
+
+        let args = ListArgs {
+            directory: Some(dir.path().display().to_string()),
+            all: false,
+            long: false,
+            pattern: Some("*.txt".to_string()),
+            json: false,
+            follow_symlinks: true,
+        };
+        let result = execute_list(&args).unwrap();
+        assert!(result.message.starts_with("2 entries matching '*.txt'"));
+        assert_eq!(result.render(false), "a.txt\nb.txt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn list_reports_permission_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "a").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let args = ListArgs {
+            directory: Some(dir.path().display().to_string()),
+            all: false,
+            long: true,
+            pattern: None,
+            json: false,
+            follow_symlinks: true,
+        };
+        let result = execute_list(&args).unwrap();
+        assert_eq!(result.entries[0].mode, Some(0o644));
+        assert!(!result.entries[0].readonly);
+        assert!(result.render(true).starts_with("rw-r--r--"));
+        assert_eq!(format_mode(0o755), "rwxr-xr-x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn list_reports_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("target"), dir.path().join("link")).unwrap();
+
+        let mut args = ListArgs {
+            directory: Some(dir.path().display().to_string()),
+            all: false,
+            long: false,
+            pattern: Some("link".to_string()),
+            json: false,
+            follow_symlinks: true,
+        };
+        let entry = &execute_list(&args).unwrap().entries[0];
+        assert!(entry.is_symlink);
+        assert!(entry.is_dir);
+
+        args.follow_symlinks = false;
+        let entry = &execute_list(&args).unwrap().entries[0];
+        assert!(entry.is_symlink);
+        assert!(!entry.is_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_without_following_keeps_directory_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        let destination = dir.path().join("destination");
+        std::fs::create_dir_all(source.join("real")).unwrap();
+        std::fs::write(source.join("real").join("inner.txt"), "inner").unwrap();
+        // Points back at its parent, so following it would loop
+        std::os::unix::fs::symlink("..", source.join("real").join("loop")).unwrap();
+
+        let mut args = CopyArgs {
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+            force: false,
+            recursive: true,
+            follow_symlinks: true,
+        };
+        let err = execute_copy(&args, false).unwrap_err();
+        assert_eq!(code(&err), FileErrorCode::InvalidPath);
+
+        args.follow_symlinks = false;
+        let result = execute_copy(&args, false).unwrap();
+        assert_eq!(result.details.as_deref(), Some("2 files copied"));
+        let link = destination.join("real").join("loop");
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_link(&link).unwrap(), Path::new(".."));
+    }
+
+    #[test]
+    fn list_result_serializes_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "abc").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let args = ListArgs {
+            directory: Some(dir.path().display().to_string()),
+            all: false,
+            long: false,
+            pattern: None,
+            json: true,
+            follow_symlinks: true,
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&execute_list(&args).unwrap().to_json().unwrap()).unwrap();
+
+        let entries = json["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["name"], "a.txt");
+        assert_eq!(entries[0]["size"], 3);
+        assert_eq!(entries[1]["is_dir"], true);
+    }
 }
 
 #[derive(Debug, Args)]
@@ -45,6 +372,14 @@ pub struct CopyArgs {
     /// Overwrite destination if it exists
     #[arg(short, long)]
     pub force: bool,
+    
+    /// Copy directories recursively
+    #[arg(short, long)]
+    pub recursive: bool,
+    
+    /// Copy symlinks inside a recursive copy as links instead of their targets
+    #[arg(long = "no-follow", action = ArgAction::SetFalse)]
+    pub follow_symlinks: bool,
 }
 
 #[derive(Debug, Args)]
@@ -72,35 +407,428 @@ pub struct ListArgs {
     /// Use long listing format
     #[arg(short, long)]
     pub long: bool,
+    
+    /// Only list entries whose names match this glob pattern (e.g. "*.rs")
+    #[arg(short, long)]
+    pub pattern: Option<String>,
+    
+    /// Print the listing as JSON
+    #[arg(long)]
+    pub json: bool,
+    
+    /// Report symlinks themselves instead of their targets
+    #[arg(long = "no-follow", action = ArgAction::SetFalse)]
+    pub follow_symlinks: bool,
+}
+
+pub const ERROR_FILE_NOT_FOUND: &str = "FILE_NOT_FOUND";
+pub const ERROR_ACCESS_DENIED: &str = "ACCESS_DENIED";
+pub const ERROR_INVALID_PATH: &str = "INVALID_PATH";
+pub const ERROR_ALREADY_EXISTS: &str = "ALREADY_EXISTS";
+
+/// Error code of a failed file command; displays and serializes as the
+/// `ERROR_*` strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileErrorCode {
+    FileNotFound,
+    AccessDenied,
+    InvalidPath,
+    AlreadyExists,
+    /// Code not known to this version
+    Other(String),
+}
+
+impl FileErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FileErrorCode::FileNotFound => ERROR_FILE_NOT_FOUND,
+            FileErrorCode::AccessDenied => ERROR_ACCESS_DENIED,
+            FileErrorCode::InvalidPath => ERROR_INVALID_PATH,
+            FileErrorCode::AlreadyExists => ERROR_ALREADY_EXISTS,
+            FileErrorCode::Other(code) => code,
+        }
+    }
 }
 
+impl From<&str> for FileErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            ERROR_FILE_NOT_FOUND => FileErrorCode::FileNotFound,
+            ERROR_ACCESS_DENIED => FileErrorCode::AccessDenied,
+            ERROR_INVALID_PATH => FileErrorCode::InvalidPath,
+            ERROR_ALREADY_EXISTS => FileErrorCode::AlreadyExists,
+            other => FileErrorCode::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for FileErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for FileErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Error returned by file commands; recover the code with
+/// `err.downcast_ref::<FileError>()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileError {
+    pub code: FileErrorCode,
+    pub message: String,
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// Outcome of a file command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCommandResult {
+    pub success: bool,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl FileCommandResult {
+    fn success(message: String) -> Self {
+        Self {
+            success: true,
+            message,
+            details: None,
+        }
+    }
+}
+
+/// A single entry of a directory listing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileEntry {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+    /// Last modification time in RFC 3339, when the platform reports it
+    pub modified: Option<String>,
+    /// Unix permission bits; `None` on platforms without them
+    pub mode: Option<u32>,
+    pub readonly: bool,
+    pub is_symlink: bool,
+}
+
+/// Outcome of the file list command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileListResult {
+    pub success: bool,
+    pub message: String,
+    pub entries: Vec<FileEntry>,
+}
+
+impl FileListResult {
+    /// Render entries one per line; `long` adds size and modification time
+    pub fn render(&self, long: bool) -> String {
+        self.entries.iter()
+            .map(|entry| {
+                let name = if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() };
+                if !long {
+                    return name;
+                }
+                let modified = entry.modified.as_deref().unwrap_or("-");
+                let mode = entry.mode.map(format_mode).unwrap_or_else(|| "-".to_string());
+                format!("{}  {:>12}  {}  {}", mode, entry.size, modified, name)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Serialize the result as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+// Dry-run convention: mutating commands take a `dry_run` flag from the global
+// `--dry-run` option. When set, they validate their arguments, touch nothing,
+// and describe the change they would have made in `FileCommandResult::message`
+// using a "Would ..." sentence.
+
 /// Execute the file copy command
-pub fn execute_copy(args: &CopyArgs) -> Result<()> {
+pub fn execute_copy(args: &CopyArgs, dry_run: bool) -> Result<FileCommandResult> {
+    let source = Path::new(&args.source);
+    let destination = Path::new(&args.destination);
+
+    let metadata = fs::metadata(source).map_err(|e| io_error(e, source))?;
+    if metadata.is_dir() {
+        if !args.recursive {
+            return Err(file_error(
+                FileErrorCode::InvalidPath,
+                format!("Source '{}' is a directory, use --recursive to copy it", args.source),
+            ));
+        }
+        return copy_directory(args, dry_run);
+    }
+    check_same_file(source, destination)?;
+    check_destination(destination, args.force)?;
+
+    if dry_run {
+        return Ok(FileCommandResult::success(
+            format!("Would copy {} to {}", args.source, args.destination),
+        ));
+    }
+
     dougu_essentials_logger::log_info(format!("Copying {} to {}", args.source, args.destination));
     
-    // Pseudo implementation
-    // In a real app, this would perform the actual file copy
+    let bytes = fs::copy(source, destination).map_err(|e| io_error(e, destination))?;
     
-    Ok(())
+    Ok(FileCommandResult {
+        success: true,
+        message: format!("Copied {} to {}", args.source, args.destination),
+        details: Some(format!("{} bytes", bytes)),
+    })
+}
+
+/// Recreate the source directory tree at the destination
+fn copy_directory(args: &CopyArgs, dry_run: bool) -> Result<FileCommandResult> {
+    let source = Path::new(&args.source);
+    let destination = Path::new(&args.destination);
+
+    let source_abs = std::path::absolute(source).map_err(|e| io_error(e, source))?;
+    let destination_abs = std::path::absolute(destination).map_err(|e| io_error(e, destination))?;
+    if destination_abs.starts_with(&source_abs) {
+        return Err(file_error(
+            FileErrorCode::InvalidPath,
+            format!("Destination '{}' is inside source '{}'", args.destination, args.source),
+        ));
+    }
+
+    // Plan the whole copy first so conflicts fail before anything is written
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    let mut links = Vec::new();
+    for entry in WalkDir::new(source).follow_links(args.follow_symlinks).sort_by_file_name() {
+        let entry = entry.map_err(|e| {
+            let path = e.path().unwrap_or(source).to_path_buf();
+            match e.into_io_error() {
+                Some(err) => io_error(err, &path),
+                None => file_error(
+                    FileErrorCode::InvalidPath,
+                    format!("Filesystem loop at '{}'", path.display()),
+                ),
+            }
+        })?;
+        let relative = entry.path().strip_prefix(source)?;
+        let target = destination.join(relative);
+        if entry.file_type().is_dir() {
+            dirs.push(target);
+        } else if entry.file_type().is_symlink() {
+            check_destination(&target, args.force)?;
+            links.push((entry.into_path(), target));
+        } else {
+            check_destination(&target, args.force)?;
+            files.push((entry.into_path(), target));
+        }
+    }
+
+    if dry_run {
+        return Ok(FileCommandResult {
+            success: true,
+            message: format!("Would copy {} to {}", args.source, args.destination),
+            details: Some(format!("{} files would be copied", files.len() + links.len())),
+        });
+    }
+
+    dougu_essentials_logger::log_info(format!(
+        "Copying directory {} to {}",
+        args.source, args.destination
+    ));
+
+    for dir in &dirs {
+        fs::create_dir_all(dir).map_err(|e| io_error(e, dir))?;
+    }
+    for (from, to) in &files {
+        fs::copy(from, to).map_err(|e| io_error(e, to))?;
+    }
+    for (from, to) in &links {
+        copy_symlink(from, to, args.force)?;
+    }
+
+    Ok(FileCommandResult {
+        success: true,
+        message: format!("Copied {} to {}", args.source, args.destination),
+        details: Some(format!("{} files copied", files.len() + links.len())),
+    })
 }
 
 /// Execute the file move command
-pub fn execute_move(args: &MoveArgs) -> Result<()> {
+pub fn execute_move(args: &MoveArgs, dry_run: bool) -> Result<FileCommandResult> {
+    let source = Path::new(&args.source);
+    let destination = Path::new(&args.destination);
+
+    let metadata = fs::metadata(source).map_err(|e| io_error(e, source))?;
+    check_same_file(source, destination)?;
+    check_destination(destination, args.force)?;
+
+    if dry_run {
+        return Ok(FileCommandResult::success(
+            format!("Would move {} to {}", args.source, args.destination),
+        ));
+    }
+
     dougu_essentials_logger::log_info(format!("Moving {} to {}", args.source, args.destination));
     
-    // Pseudo implementation
-    // In a real app, this would perform the actual file move
+    match fs::rename(source, destination) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::CrossesDevices && metadata.is_file() => {
+            // Rename cannot cross filesystems; fall back to copy and delete
+            fs::copy(source, destination).map_err(|e| io_error(e, destination))?;
+            fs::remove_file(source).map_err(|e| io_error(e, source))?;
+        }
+        Err(e) => return Err(io_error(e, source)),
+    }
     
-    Ok(())
+    Ok(FileCommandResult::success(
+        format!("Moved {} to {}", args.source, args.destination),
+    ))
 }
 
 /// Execute the file list command
-pub fn execute_list(args: &ListArgs) -> Result<()> {
+pub fn execute_list(args: &ListArgs) -> Result<FileListResult> {
     let dir = args.directory.as_deref().unwrap_or(".");
     dougu_essentials_logger::log_info(format!("Listing directory: {}", dir));
     
-    // Pseudo implementation
-    // In a real app, this would list the directory contents
+    let pattern = args.pattern.as_deref()
+        .map(|p| Pattern::new(p).map_err(|e| anyhow!("Invalid pattern '{}': {}", p, e)))
+        .transpose()?;
+
+    let path = Path::new(dir);
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path).map_err(|e| io_error(e, path))? {
+        let entry = entry.map_err(|e| io_error(e, path))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !args.all && name.starts_with('.') {
+            continue;
+        }
+        if pattern.as_ref().is_some_and(|p| !p.matches(&name)) {
+            continue;
+        }
+        let is_symlink = entry.file_type().map_err(|e| io_error(e, &entry.path()))?.is_symlink();
+        // A dangling link has no target to follow, so report the link itself
+        let metadata = if is_symlink && args.follow_symlinks {
+            fs::metadata(entry.path()).or_else(|_| entry.metadata())
+        } else {
+            entry.metadata()
+        }.map_err(|e| io_error(e, &entry.path()))?;
+        entries.push(FileEntry {
+            name,
+            size: metadata.len(),
+            is_dir: metadata.is_dir(),
+            modified: metadata.modified()
+                .ok()
+                .map(|t| DateTime::<Utc>::from(t).to_rfc3339_opts(SecondsFormat::Secs, true)),
+            mode: permission_mode(&metadata),
+            readonly: metadata.permissions().readonly(),
+            is_symlink,
+        });
+    }
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
     
+    Ok(FileListResult {
+        success: true,
+        message: match &args.pattern {
+            Some(p) => format!("{} entries matching '{}' in {}", entries.len(), p, dir),
+            None => format!("{} entries in {}", entries.len(), dir),
+        },
+        entries,
+    })
+}
+
+#[cfg(unix)]
+fn permission_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn permission_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+/// Render permission bits as `rwxr-xr-x`
+pub fn format_mode(mode: u32) -> String {
+    (0..9)
+        .map(|i| {
+            let bit = 1 << (8 - i);
+            if mode & bit == 0 {
+                '-'
+            } else {
+                ['r', 'w', 'x'][i % 3]
+            }
+        })
+        .collect()
+}
+
+/// Recreate the symlink `from` at `to`, pointing at the same target
+fn copy_symlink(from: &Path, to: &Path, force: bool) -> Result<()> {
+    let target = fs::read_link(from).map_err(|e| io_error(e, from))?;
+    if force && fs::symlink_metadata(to).is_ok() {
+        fs::remove_file(to).map_err(|e| io_error(e, to))?;
+    }
+    #[cfg(unix)]
+    let created = std::os::unix::fs::symlink(&target, to);
+    #[cfg(windows)]
+    let created = if fs::metadata(from).is_ok_and(|m| m.is_dir()) {
+        std::os::windows::fs::symlink_dir(&target, to)
+    } else {
+        std::os::windows::fs::symlink_file(&target, to)
+    };
+    created.map_err(|e| io_error(e, to))
+}
+
+/// Reject a destination that resolves to the source itself, which
+/// `fs::copy` would truncate before reading
+fn check_same_file(source: &Path, destination: &Path) -> Result<()> {
+    let source_real = fs::canonicalize(source).ok();
+    if source_real.is_some() && source_real == fs::canonicalize(destination).ok() {
+        return Err(file_error(
+            FileErrorCode::InvalidPath,
+            format!("Source and destination '{}' are the same file", destination.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject an existing destination unless overwriting is forced
+fn check_destination(destination: &Path, force: bool) -> Result<()> {
+    // symlink_metadata so a dangling symlink counts as existing
+    if fs::symlink_metadata(destination).is_ok() && !force {
+        return Err(file_error(
+            FileErrorCode::AlreadyExists,
+            format!("Destination '{}' already exists, use --force to overwrite", destination.display()),
+        ));
+    }
     Ok(())
 }
+
+/// Map an IO error to the file command error codes
+fn io_error(err: io::Error, path: &Path) -> anyhow::Error {
+    let code = match err.kind() {
+        ErrorKind::NotFound => FileErrorCode::FileNotFound,
+        ErrorKind::PermissionDenied => FileErrorCode::AccessDenied,
+        ErrorKind::InvalidInput
+        | ErrorKind::InvalidFilename
+        | ErrorKind::NotADirectory
+        | ErrorKind::IsADirectory => FileErrorCode::InvalidPath,
+        _ => return anyhow::Error::new(err).context(format!("'{}'", path.display())),
+    };
+    file_error(code, format!("'{}': {}", path.display(), err))
+}
+
+fn file_error(code: FileErrorCode, message: String) -> anyhow::Error {
+    FileError { code, message }.into()
+}
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand::Rng;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const AUTHORIZE_URL: &str = "https://www.dropbox.com/oauth2/authorize";
+const TOKEN_URL: &str = "https://api.dropboxapi.com/oauth2/token";
+
+/// Characters allowed in a PKCE code verifier (RFC 7636 unreserved set)
+const VERIFIER_CHARSET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+
+/// Refresh access tokens this many seconds before they actually expire
+const EXPIRY_MARGIN_SECS: u64 = 60;
+
+/// PKCE verifier and its S256 challenge
+#[derive(Debug, Clone)]
+pub struct PkceChallenge {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl PkceChallenge {
+    /// Generate a random verifier and derive its challenge
+    pub fn generate() -> Self {
+        let mut rng = rand::thread_rng();
+        let verifier: String = (0..64)
+            .map(|_| VERIFIER_CHARSET[rng.gen_range(0..VERIFIER_CHARSET.len())] as char)
+            .collect();
+        Self::from_verifier(verifier)
+    }
+
+    /// Derive the S256 challenge for a known verifier
+    pub fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self { verifier, challenge }
+    }
+}
+
+/// Requests sent to the OAuth token endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenRequest {
+    /// Exchange the code the user copied from the authorization page
+    AuthorizationCode { code: String, code_verifier: String },
+    /// Obtain a new access token from a refresh token
+    RefreshToken { refresh_token: String },
+}
+
+impl TokenRequest {
+    /// Form parameters for the token endpoint
+    pub fn form(&self, app_key: &str) -> Vec<(&'static str, String)> {
+        match self {
+            TokenRequest::AuthorizationCode { code, code_verifier } => vec![
+                ("grant_type", "authorization_code".to_string()),
+                ("code", code.clone()),
+                ("client_id", app_key.to_string()),
+                ("code_verifier", code_verifier.clone()),
+            ],
+            TokenRequest::RefreshToken { refresh_token } => vec![
+                ("grant_type", "refresh_token".to_string()),
+                ("refresh_token", refresh_token.clone()),
+                ("client_id", app_key.to_string()),
+            ],
+        }
+    }
+}
+
+/// Successful response of the token endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub expires_in: Option<u64>,
+    pub refresh_token: Option<String>,
+}
+
+/// Token persisted between runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredToken {
+    pub app_key: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Expiry as seconds since the Unix epoch
+    pub expires_at: Option<u64>,
+}
+
+impl StoredToken {
+    /// Default location: `<config dir>/dougu/dropbox-token.json`
+    pub fn default_path() -> Result<PathBuf> {
+        let dir = dirs::config_dir().ok_or_else(|| anyhow!("Unable to determine config directory"))?;
+        Ok(dir.join("dougu").join("dropbox-token.json"))
+    }
+
+    /// Load a token from the given path
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("No stored Dropbox token at '{}', run `dougu dropbox auth`", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the token, creating parent directories as needed.
+    /// On unix the file is readable by the owner only.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // The mode only applies to new files; tighten one left by an older version
+            if path.exists() {
+                fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        let mut file = options.open(path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Whether the access token expires within the refresh margin of `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| now + EXPIRY_MARGIN_SECS >= expires_at)
+    }
+
+    /// Apply a token endpoint response, keeping the refresh token if none was returned
+    pub fn update(&mut self, response: TokenResponse, now: u64) {
+        self.access_token = response.access_token;
+        self.expires_at = response.expires_in.map(|secs| now + secs);
+        if response.refresh_token.is_some() {
+            self.refresh_token = response.refresh_token;
+        }
+    }
+}
+
+/// OAuth 2.0 authorization code flow with PKCE for a Dropbox app.
+///
+/// Dropbox has no device-code grant. Instead the user opens the authorization
+/// URL, approves the app and pastes the displayed code back into the CLI.
+pub struct DropboxAuth {
+    client: Client,
+    app_key: String,
+}
+
+impl DropboxAuth {
+    /// Create a new authenticator for the given app key
+    pub fn new(app_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            app_key,
+        }
+    }
+
+    /// URL the user opens to authorize the app
+    pub fn authorize_url(&self, pkce: &PkceChallenge) -> String {
+        format!(
+            "{}?client_id={}&response_type=code&token_access_type=offline&code_challenge={}&code_challenge_method=S256",
+            AUTHORIZE_URL, self.app_key, pkce.challenge
+        )
+    }
+
+    /// Exchange an authorization code for a token
+    pub async fn exchange_code(&self, code: &str, pkce: &PkceChallenge) -> Result<StoredToken> {
+        let response = self.request_token(&TokenRequest::AuthorizationCode {
+            code: code.trim().to_string(),
+            code_verifier: pkce.verifier.clone(),
+        }).await?;
+
+        let mut token = StoredToken {
+            app_key: self.app_key.clone(),
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at: None,
+        };
+        token.update(response, now_secs());
+        Ok(token)
+    }
+
+    /// Refresh the access token in place
+    pub async fn refresh(&self, token: &mut StoredToken) -> Result<()> {
+        let refresh_token = token.refresh_token.clone()
+            .ok_or_else(|| anyhow!("Stored Dropbox token has no refresh token, run `dougu dropbox auth`"))?;
+        let response = self.request_token(&TokenRequest::RefreshToken { refresh_token }).await?;
+        token.update(response, now_secs());
+        Ok(())
+    }
+
+    async fn request_token(&self, request: &TokenRequest) -> Result<TokenResponse> {
+        let response = self.client
+            .post(TOKEN_URL)
+            .form(&request.form(&self.app_key))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Dropbox token request failed ({}): {}", status, body));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+/// Load the stored token, refreshing and re-saving it when expired
+pub async fn load_access_token() -> Result<String> {
+    let path = StoredToken::default_path()?;
+    let mut token = StoredToken::load(&path)?;
+
+    if token.is_expired(now_secs()) {
+        dougu_essentials_logger::log_info("Refreshing Dropbox access token");
+        DropboxAuth::new(token.app_key.clone()).refresh(&mut token).await?;
+        token.save(&path)?;
+    }
+    Ok(token.access_token)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_s256_challenge() {
+        // Example from RFC 7636 appendix B
+        let pkce = PkceChallenge::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(pkce.challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+
+        let generated = PkceChallenge::generate();
+        assert_eq!(generated.verifier.len(), 64);
+    }
+
+    #[test]
+    fn builds_refresh_request() {
+        let request = TokenRequest::RefreshToken { refresh_token: "refresh".to_string() };
+        assert_eq!(request.form("app"), vec![
+            ("grant_type", "refresh_token".to_string()),
+            ("refresh_token", "refresh".to_string()),
+            ("client_id", "app".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn refreshes_expired_token() {
+        let mut token = StoredToken {
+            app_key: "app".to_string(),
+            access_token: "old".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(1_000),
+        };
+        assert!(!token.is_expired(100));
+        assert!(token.is_expired(950));
+
+        token.update(TokenResponse {
+            access_token: "new".to_string(),
+            expires_in: Some(14_400),
+            refresh_token: None,
+        }, 1_000);
+        assert_eq!(token.access_token, "new");
+        assert_eq!(token.expires_at, Some(15_400));
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dougu").join("token.json");
+        token.save(&path).unwrap();
+        assert_eq!(StoredToken::load(&path).unwrap(), token);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saves_token_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let token = StoredToken {
+            app_key: "app".to_string(),
+            access_token: "access".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: None,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token.json");
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        token.save(&path).unwrap();
+        assert_eq!(mode(&path), 0o600);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        token.save(&path).unwrap();
+        assert_eq!(mode(&path), 0o600);
+    }
+}
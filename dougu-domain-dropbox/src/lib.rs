@@ -1,11 +1,22 @@
 use anyhow::{Result, anyhow};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::time::Duration;
 
+mod auth;
+mod retry;
+
+pub use auth::{DropboxAuth, PkceChallenge, StoredToken, TokenRequest, TokenResponse, load_access_token};
+pub use retry::RetryPolicy;
+
+const API_URL: &str = "https://api.dropboxapi.com/2";
+
 pub struct DropboxClient {
     client: Client,
     token: String,
+    retry_policy: RetryPolicy,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,6 +25,7 @@ pub struct DropboxFile {
     pub name: String,
     pub size: u64,
     pub modified: String,
+    pub is_folder: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,6 +35,143 @@ pub struct DropboxListResult {
     pub has_more: bool,
 }
 
+/// Metadata entry returned by the list_folder endpoints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum DropboxEntry {
+    File {
+        name: String,
+        path_display: Option<String>,
+        size: u64,
+        server_modified: String,
+    },
+    Folder {
+        name: String,
+        path_display: Option<String>,
+    },
+    /// Only reported by `list_folder_continue` for entries removed since the cursor
+    Deleted {
+        name: String,
+        path_display: Option<String>,
+    },
+}
+
+impl DropboxEntry {
+    /// Entry name
+    pub fn name(&self) -> &str {
+        match self {
+            DropboxEntry::File { name, .. }
+            | DropboxEntry::Folder { name, .. }
+            | DropboxEntry::Deleted { name, .. } => name,
+        }
+    }
+
+    /// Display path, falling back to the name
+    pub fn path(&self) -> &str {
+        let path_display = match self {
+            DropboxEntry::File { path_display, .. }
+            | DropboxEntry::Folder { path_display, .. }
+            | DropboxEntry::Deleted { path_display, .. } => path_display,
+        };
+        path_display.as_deref().unwrap_or(self.name())
+    }
+}
+
+/// Argument of `files/list_folder` and `files/list_folder/get_latest_cursor`
+#[derive(Debug, Clone, Serialize)]
+pub struct ListFolderArg {
+    pub path: String,
+    pub recursive: bool,
+}
+
+/// Argument of `files/list_folder/continue`
+#[derive(Debug, Clone, Serialize)]
+pub struct ListFolderContinueArg {
+    pub cursor: String,
+}
+
+/// Result of `files/list_folder` and `files/list_folder/continue`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListFolderResult {
+    pub entries: Vec<DropboxEntry>,
+    pub cursor: String,
+    pub has_more: bool,
+}
+
+/// Result of `files/list_folder/get_latest_cursor`
+#[derive(Debug, Clone, Deserialize)]
+pub struct LatestCursorResult {
+    pub cursor: String,
+}
+
+/// Error response of a Dropbox API endpoint
+#[derive(Debug, Clone)]
+pub struct DropboxApiError {
+    pub endpoint: String,
+    pub status: u16,
+    pub body: String,
+}
+
+impl fmt::Display for DropboxApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dropbox API {} failed ({}): {}", self.endpoint, self.status, self.body)
+    }
+}
+
+impl std::error::Error for DropboxApiError {}
+
+/// Settings for a new shared link
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SharedLinkSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_password: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_password: Option<String>,
+    /// Expiry in `%Y-%m-%dT%H:%M:%SZ` format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+}
+
+impl SharedLinkSettings {
+    /// Require the given password to open the link
+    pub fn with_password(mut self, password: String) -> Self {
+        self.require_password = Some(true);
+        self.link_password = Some(password);
+        self
+    }
+    
+    /// Expire the link at the given UTC timestamp
+    pub fn with_expires(mut self, expires: String) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+}
+
+/// A shared link as returned by the sharing endpoints
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SharedLink {
+    pub url: String,
+    pub name: String,
+    pub expires: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CreateSharedLinkArg {
+    path: String,
+    settings: SharedLinkSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ListSharedLinksArg {
+    path: String,
+    direct_only: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ListSharedLinksResult {
+    links: Vec<SharedLink>,
+}
+
 impl DropboxClient {
     /// Create a new Dropbox client with the given access token
     pub fn new(token: String) -> Self {
@@ -31,37 +180,151 @@ impl DropboxClient {
             .build()
             .expect("Failed to create HTTP client");
         
-        Self { client, token }
+        Self {
+            client,
+            token,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+    
+    /// Replace the policy used to retry rate-limited (429/503) requests
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+    
+    /// Create a client from the token saved by `dougu dropbox auth`, refreshing it if expired
+    pub async fn from_stored_token() -> Result<Self> {
+        Ok(Self::new(load_access_token().await?))
     }
     
-    /// List files in a given Dropbox path
+    /// List the first page of files in a given Dropbox path
     pub async fn list_files(&self, path: &str) -> Result<DropboxListResult> {
-        // This is a pseudo implementation
-        // In a real application, this would make actual API calls to Dropbox
-        
         dougu_essentials_logger::log_info(format!("Listing files from path: {}", path));
         
-        // For demo purposes, return dummy data
+        let result: ListFolderResult = self.rpc("files/list_folder", &list_folder_arg(path)).await?;
+        
         Ok(DropboxListResult {
-            files: vec![
-                DropboxFile {
-                    path: format!("{}/document.txt", path),
-                    name: "document.txt".to_string(),
-                    size: 1024,
-                    modified: "2023-11-01T12:00:00Z".to_string(),
-                },
-                DropboxFile {
-                    path: format!("{}/image.jpg", path),
-                    name: "image.jpg".to_string(),
-                    size: 2048,
-                    modified: "2023-11-02T15:30:00Z".to_string(),
-                },
-            ],
-            cursor: Some("dummy_cursor".to_string()),
-            has_more: false,
+            files: result.entries.into_iter().filter_map(to_file).collect(),
+            cursor: Some(result.cursor),
+            has_more: result.has_more,
         })
     }
     
+    /// List every entry in a folder, following pagination, and return the
+    /// cursor to pass to `list_folder_continue` for later changes
+    pub async fn list_folder_with_cursor(&self, path: &str) -> Result<(Vec<DropboxEntry>, String)> {
+        let result: ListFolderResult = self.rpc("files/list_folder", &list_folder_arg(path)).await?;
+        
+        let mut entries = result.entries;
+        if !result.has_more {
+            return Ok((entries, result.cursor));
+        }
+        let (rest, cursor) = self.list_folder_changes(&result.cursor).await?;
+        entries.extend(rest);
+        Ok((entries, cursor))
+    }
+    
+    /// List every change since `cursor`, following pagination, and return the
+    /// cursor for the next call
+    pub async fn list_folder_changes(&self, cursor: &str) -> Result<(Vec<DropboxEntry>, String)> {
+        let mut entries = Vec::new();
+        let mut cursor = cursor.to_string();
+        loop {
+            let (page, next_cursor, has_more) = self.list_folder_continue(&cursor).await?;
+            entries.extend(page);
+            cursor = next_cursor;
+            if !has_more {
+                return Ok((entries, cursor));
+            }
+        }
+    }
+    
+    /// Fetch one page of changes since `cursor`: entries, the next cursor and whether more remain
+    pub async fn list_folder_continue(&self, cursor: &str) -> Result<(Vec<DropboxEntry>, String, bool)> {
+        let arg = ListFolderContinueArg { cursor: cursor.to_string() };
+        let result: ListFolderResult = self.rpc("files/list_folder/continue", &arg).await?;
+        Ok((result.entries, result.cursor, result.has_more))
+    }
+    
+    /// Get a cursor for the current state of a folder without listing it,
+    /// for polling only future changes
+    pub async fn list_folder_latest_cursor(&self, path: &str) -> Result<String> {
+        let result: LatestCursorResult = self
+            .rpc("files/list_folder/get_latest_cursor", &list_folder_arg(path))
+            .await?;
+        Ok(result.cursor)
+    }
+    
+    /// Call an RPC-style API endpoint with a JSON argument
+    async fn rpc<A: Serialize, R: DeserializeOwned>(&self, endpoint: &str, arg: &A) -> Result<R> {
+        let url = format!("{}/{}", API_URL, endpoint);
+        let response = retry::send_with_retry(&self.retry_policy, || {
+            self.client
+                .post(&url)
+                .bearer_auth(&self.token)
+                .json(arg)
+                .send()
+        }).await?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DropboxApiError {
+                endpoint: endpoint.to_string(),
+                status: status.as_u16(),
+                body,
+            }.into());
+        }
+        Ok(response.json().await?)
+    }
+    
+    /// Create a shared link for `path`. If the file already has a link, that
+    /// existing link is returned instead and `settings` are not applied.
+    pub async fn create_shared_link(&self, path: &str, settings: &SharedLinkSettings) -> Result<SharedLink> {
+        dougu_essentials_logger::log_info(format!("Creating shared link for: {}", path));
+        
+        let arg = CreateSharedLinkArg {
+            path: path.to_string(),
+            settings: settings.clone(),
+        };
+        let err = match self.rpc("sharing/create_shared_link_with_settings", &arg).await {
+            Ok(link) => return Ok(link),
+            Err(err) => err,
+        };
+        
+        let existing = match err.downcast_ref::<DropboxApiError>() {
+            Some(api_error) if api_error.status == 409 => existing_link_from_conflict(&api_error.body),
+            _ => return Err(err),
+        };
+        let Some(existing) = existing else {
+            return Err(err);
+        };
+        
+        if *settings != SharedLinkSettings::default() {
+            dougu_essentials_logger::log_warning(format!(
+                "A shared link already exists for {}; requested settings were not applied",
+                path
+            ));
+        }
+        match existing {
+            Some(link) => Ok(link),
+            None => self.existing_shared_link(path).await,
+        }
+    }
+    
+    /// Fetch the existing direct shared link for `path`
+    async fn existing_shared_link(&self, path: &str) -> Result<SharedLink> {
+        let arg = ListSharedLinksArg {
+            path: path.to_string(),
+            direct_only: true,
+        };
+        let result: ListSharedLinksResult = self.rpc("sharing/list_shared_links", &arg).await?;
+        result.links.into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No shared link found for {}", path))
+    }
+    
     /// Download a file from Dropbox
     pub async fn download_file(&self, path: &str) -> Result<Vec<u8>> {
         // Pseudo implementation
@@ -72,7 +335,7 @@ impl DropboxClient {
     }
     
     /// Upload a file to Dropbox
-    pub async fn upload_file(&self, path: &str, content: Vec<u8>) -> Result<DropboxFile> {
+    pub async fn upload_file(&self, path: &str, _content: Vec<u8>) -> Result<DropboxFile> {
         // Pseudo implementation
         dougu_essentials_logger::log_info(format!("Uploading file to: {}", path));
         
@@ -81,6 +344,52 @@ impl DropboxClient {
     }
 }
 
+/// Inspect a 409 error body for `shared_link_already_exists`.
+///
+/// Returns `None` for other errors, `Some(Some(link))` when the body carries
+/// the existing link's metadata, and `Some(None)` when it must be fetched.
+fn existing_link_from_conflict(body: &str) -> Option<Option<SharedLink>> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let error = value.get("error")?;
+    if error.get(".tag")?.as_str()? != "shared_link_already_exists" {
+        return None;
+    }
+    let metadata = error.get("shared_link_already_exists")
+        .and_then(|e| e.get("metadata"))
+        .and_then(|m| serde_json::from_value(m.clone()).ok());
+    Some(metadata)
+}
+
+/// Dropbox addresses the root folder as an empty path
+fn list_folder_arg(path: &str) -> ListFolderArg {
+    let path = if path == "/" { "" } else { path };
+    ListFolderArg {
+        path: path.to_string(),
+        recursive: false,
+    }
+}
+
+fn to_file(entry: DropboxEntry) -> Option<DropboxFile> {
+    let path = entry.path().to_string();
+    match entry {
+        DropboxEntry::File { name, size, server_modified, .. } => Some(DropboxFile {
+            path,
+            name,
+            size,
+            modified: server_modified,
+            is_folder: false,
+        }),
+        DropboxEntry::Folder { name, .. } => Some(DropboxFile {
+            path,
+            name,
+            size: 0,
+            modified: String::new(),
+            is_folder: true,
+        }),
+        DropboxEntry::Deleted { .. } => None,
+    }
+}
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -94,4 +403,71 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn list_folder_serialization() {
+        let arg = serde_json::to_value(list_folder_arg("/")).unwrap();
+        assert_eq!(arg, serde_json::json!({"path": "", "recursive": false}));
+
+        let result: ListFolderResult = serde_json::from_str(r#"{
+            "entries": [
+                {".tag": "file", "name": "a.txt", "path_display": "/a.txt", "id": "id:1",
+                 "size": 12, "server_modified": "2024-01-01T00:00:00Z", "rev": "1"},
+                {".tag": "folder", "name": "docs", "path_display": "/docs", "id": "id:2"},
+                {".tag": "deleted", "name": "old.txt", "path_display": "/old.txt"}
+            ],
+            "cursor": "AAE",
+            "has_more": true
+        }"#).unwrap();
+
+        assert_eq!(result.cursor, "AAE");
+        assert!(result.has_more);
+        assert_eq!(result.entries[0], DropboxEntry::File {
+            name: "a.txt".to_string(),
+            path_display: Some("/a.txt".to_string()),
+            size: 12,
+            server_modified: "2024-01-01T00:00:00Z".to_string(),
+        });
+        assert_eq!(result.entries[2].path(), "/old.txt");
+
+        let files: Vec<DropboxFile> = result.entries.into_iter().filter_map(to_file).collect();
+        assert_eq!(files.len(), 2);
+        assert!(files[1].is_folder);
+    }
+
+    #[test]
+    fn shared_link_settings_serialization() {
+        let settings = SharedLinkSettings::default()
+            .with_password("secret".to_string())
+            .with_expires("2030-01-01T00:00:00Z".to_string());
+        assert_eq!(serde_json::to_value(&settings).unwrap(), serde_json::json!({
+            "require_password": true,
+            "link_password": "secret",
+            "expires": "2030-01-01T00:00:00Z",
+        }));
+        assert_eq!(serde_json::to_value(SharedLinkSettings::default()).unwrap(), serde_json::json!({}));
+    }
+
+    #[test]
+    fn shared_link_already_exists_fallback() {
+        let with_metadata = r#"{
+            "error_summary": "shared_link_already_exists/metadata/..",
+            "error": {
+                ".tag": "shared_link_already_exists",
+                "shared_link_already_exists": {
+                    ".tag": "metadata",
+                    "metadata": {".tag": "file", "url": "https://www.dropbox.com/s/abc/a.txt?dl=0",
+                                 "name": "a.txt", "path_lower": "/a.txt"}
+                }
+            }
+        }"#;
+        let link = existing_link_from_conflict(with_metadata).unwrap().unwrap();
+        assert_eq!(link.url, "https://www.dropbox.com/s/abc/a.txt?dl=0");
+
+        let without_metadata = r#"{"error": {".tag": "shared_link_already_exists"}}"#;
+        assert_eq!(existing_link_from_conflict(without_metadata), Some(None));
+
+        let other = r#"{"error": {".tag": "email_not_verified"}}"#;
+        assert_eq!(existing_link_from_conflict(other), None);
+    }
 }
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Response, StatusCode};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// How requests are retried when Dropbox answers 429 or 503
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first request
+    pub max_attempts: u32,
+    /// Backoff for the first retry when no `Retry-After` header is sent; doubles per retry
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff delay
+    pub max_delay: Duration,
+    /// Give up instead of waiting past this much total time
+    pub deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            deadline: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retrying after the given attempt (1-based)
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let delay = retry_after.unwrap_or_else(|| {
+            self.base_delay.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        });
+        delay.min(self.max_delay)
+    }
+}
+
+/// Whether the response status asks the client to back off
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// Send a request, retrying on 429/503 according to `policy`.
+///
+/// Once attempts or the deadline are exhausted the last response is returned
+/// as is, so callers report the rate-limit error like any other failure.
+pub async fn send_with_retry<F, Fut>(policy: &RetryPolicy, mut send: F) -> Result<Response>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = reqwest::Result<Response>>,
+{
+    let started = Instant::now();
+    let mut attempt = 1;
+    loop {
+        let response = send().await?;
+        if !is_retryable(response.status()) || attempt >= policy.max_attempts {
+            return Ok(response);
+        }
+
+        let retry_after = response.headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let delay = policy.delay(attempt, retry_after);
+        if started.elapsed() + delay > policy.deadline {
+            return Ok(response);
+        }
+
+        dougu_essentials_logger::log_warning(format!(
+            "Dropbox responded {}, retrying in {:?} (attempt {}/{})",
+            response.status(), delay, attempt + 1, policy.max_attempts
+        ));
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn response(status: u16, retry_after: Option<&str>) -> reqwest::Result<Response> {
+        let mut builder = http::Response::builder().status(status);
+        if let Some(secs) = retry_after {
+            builder = builder.header("Retry-After", secs);
+        }
+        Ok(Response::from(builder.body("").unwrap()))
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            deadline: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limit_until_success() {
+        let mut responses = VecDeque::from([
+            response(429, Some("0")),
+            response(503, None),
+            response(200, None),
+        ]);
+        let mut attempts = 0;
+
+        let result = send_with_retry(&policy(5), || {
+            attempts += 1;
+            let next = responses.pop_front().unwrap();
+            async move { next }
+        }).await.unwrap();
+
+        assert_eq!(result.status(), StatusCode::OK);
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn stops_after_max_attempts() {
+        let mut attempts = 0;
+        let result = send_with_retry(&policy(2), || {
+            attempts += 1;
+            async { response(429, Some("0")) }
+        }).await.unwrap();
+
+        assert_eq!(result.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(3, None), Duration::from_secs(4));
+        assert_eq!(policy.delay(10, None), Duration::from_secs(60));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(7))), Duration::from_secs(7));
+    }
+}
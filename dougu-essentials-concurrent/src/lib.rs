@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Runs tasks concurrently with a bounded number in flight, while yielding
+/// results in the order the tasks were submitted.
+///
+/// Results that complete early are held in an index-keyed buffer until every
+/// earlier task has been yielded.
+pub struct OrderedConcurrent<T> {
+    semaphore: Arc<Semaphore>,
+    tasks: JoinSet<(usize, T)>,
+    buffer: BTreeMap<usize, T>,
+    next_submit: usize,
+    next_yield: usize,
+}
+
+impl<T: Send + 'static> OrderedConcurrent<T> {
+    /// Create a new runner allowing at most `limit` tasks to run at once
+    pub fn new(limit: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit.max(1))),
+            tasks: JoinSet::new(),
+            buffer: BTreeMap::new(),
+            next_submit: 0,
+            next_yield: 0,
+        }
+    }
+
+    /// Submit a task; it starts as soon as a concurrency slot is free
+    pub fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let index = self.next_submit;
+        self.next_submit += 1;
+
+        let semaphore = self.semaphore.clone();
+        self.tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("Semaphore is never closed");
+            (index, task.await)
+        });
+    }
+
+    /// Number of submitted tasks whose results have not been yielded yet
+    pub fn len(&self) -> usize {
+        self.next_submit - self.next_yield
+    }
+
+    /// Whether all submitted results have been yielded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Wait for the next result in submission order
+    pub async fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(result) = self.buffer.remove(&self.next_yield) {
+                self.next_yield += 1;
+                return Some(result);
+            }
+
+            match self.tasks.join_next().await? {
+                Ok((index, result)) => {
+                    self.buffer.insert(index, result);
+                }
+                Err(err) => std::panic::resume_unwind(err.into_panic()),
+            }
+        }
+    }
+
+    /// Wait for all tasks and collect their results in submission order
+    pub async fn collect(mut self) -> Vec<T> {
+        let mut results = Vec::with_capacity(self.len());
+        while let Some(result) = self.next().await {
+            results.push(result);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn collects_in_submission_order() {
+        let mut runner = OrderedConcurrent::new(16);
+        for i in 0..100u64 {
+            runner.spawn(async move {
+                tokio::time::sleep(Duration::from_millis((100 - i) % 13)).await;
+                i
+            });
+        }
+
+        let results = runner.collect().await;
+        assert_eq!(results, (0..100).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn limits_tasks_in_flight() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut runner = OrderedConcurrent::new(3);
+        for _ in 0..20 {
+            let running = running.clone();
+            let peak = peak.clone();
+            runner.spawn(async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+
+        assert_eq!(runner.collect().await.len(), 20);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+}
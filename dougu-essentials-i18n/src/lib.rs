@@ -1,16 +1,147 @@
 use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use log::warn;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::fs::File;
 use std::io::Read;
+use std::sync::{Arc, Mutex};
 
 type LocaleMap = HashMap<String, String>;
 
+/// Languages written right to left
+const RTL_LANGUAGES: &[&str] = &["ar", "he", "fa", "ur"];
+
+/// Direction in which text of a locale is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+/// A parsed locale identifier such as `ja`, `ja-JP`, `zh-Hant-TW` or `ja_JP.UTF-8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+}
+
+impl Locale {
+    /// Lowercase language subtag, e.g. `ja`
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Script subtag if present, e.g. `Hant`
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Region subtag if present, e.g. `JP`
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Whether the language is written right to left
+    pub fn is_rtl(&self) -> bool {
+        RTL_LANGUAGES.contains(&self.language.as_str())
+    }
+
+    /// Text direction hint for output alignment
+    pub fn text_direction(&self) -> TextDirection {
+        if self.is_rtl() {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+}
+
+/// Locale value that requests detection of the system locale
+pub const AUTO_LOCALE: &str = "auto";
+
+/// Resolve a user-supplied locale value. `auto` detects the system locale
+/// and yields its language (e.g. `ja`); any other value is returned as-is.
+pub fn resolve_locale(value: &str, default_locale: &str) -> String {
+    resolve_locale_with(value, default_locale, system_locale)
+}
+
+fn resolve_locale_with(
+    value: &str,
+    default_locale: &str,
+    detect: impl FnOnce() -> Option<String>,
+) -> String {
+    if value != AUTO_LOCALE {
+        return value.to_string();
+    }
+    detect()
+        .and_then(|l| Locale::from_str(&l).ok())
+        .map(|l| l.language().to_string())
+        .unwrap_or_else(|| default_locale.to_string())
+}
+
+/// System locale via the platform API, falling back to `LANG`
+fn system_locale() -> Option<String> {
+    sys_locale::get_locale()
+        .or_else(|| std::env::var("LANG").ok())
+        .filter(|l| !matches!(l.split('.').next(), Some("" | "C" | "POSIX")))
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let tag = s.split(['.', '@']).next().unwrap_or(s);
+        let mut parts = tag.split(['-', '_']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(anyhow!("Invalid locale '{}'", s));
+        }
+        let mut next = parts.next();
+        // A four-letter alphabetic subtag is a script, which precedes the region
+        let script = next
+            .filter(|p| p.len() == 4 && p.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|p| p[..1].to_ascii_uppercase() + &p[1..].to_ascii_lowercase());
+        if script.is_some() {
+            next = parts.next();
+        }
+        let region = next.map(|r| r.to_ascii_uppercase());
+
+        Ok(Self { language, script, region })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.language)?;
+        if let Some(script) = &self.script {
+            write!(f, "-{}", script)?;
+        }
+        if let Some(region) = &self.region {
+            write!(f, "-{}", region)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct I18n {
     locales: HashMap<String, LocaleMap>,
     current_locale: String,
+    /// Missing in data serialized before fallback chains; the current locale is used then
+    #[serde(default)]
+    default_locale: Option<String>,
+    #[serde(default)]
+    fallback: Vec<String>,
+    #[serde(default)]
+    strict: bool,
+    #[serde(skip)]
+    reported_missing: Arc<Mutex<HashSet<String>>>,
 }
 
 impl I18n {
@@ -19,6 +150,10 @@ impl I18n {
         Self {
             locales: HashMap::new(),
             current_locale: default_locale.to_string(),
+            default_locale: Some(default_locale.to_string()),
+            fallback: Vec::new(),
+            strict: false,
+            reported_missing: Arc::default(),
         }
     }
 
@@ -34,29 +169,323 @@ impl I18n {
         Ok(())
     }
 
-    /// Set current locale
+    /// Load and merge every `i18n-{locale}.json` or `{locale}.json` found under `dir`.
+    /// Keys already loaded for the locale win; conflicts are logged as warnings.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, locale: &str, dir: P) -> Result<()> {
+        let names = [format!("i18n-{}.json", locale), format!("{}.json", locale)];
+        let mut files = Vec::new();
+        collect_files(dir.as_ref(), &names, &mut files)?;
+        if files.is_empty() {
+            return Err(anyhow!(
+                "No translation files for locale '{}' found in '{}'",
+                locale,
+                dir.as_ref().display()
+            ));
+        }
+
+        // Parse everything first so a broken file leaves the locale untouched
+        let parsed = files.into_iter()
+            .map(|path| {
+                let content = std::fs::read_to_string(&path)?;
+                let translations: LocaleMap = serde_json::from_str(&content)
+                    .map_err(|e| anyhow!("Invalid translation file '{}': {}", path.display(), e))?;
+                Ok((path, translations))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let locale_map = self.locales.entry(locale.to_string()).or_default();
+        for (path, translations) in parsed {
+            for (key, value) in translations {
+                if locale_map.contains_key(&key) {
+                    warn!("Duplicate translation key '{}' in '{}' ignored", key, path.display());
+                    continue;
+                }
+                locale_map.insert(key, value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add translations for a locale, overriding existing keys
+    pub fn add_translations(&mut self, locale: &str, translations: HashMap<String, String>) {
+        self.locales.entry(locale.to_string())
+            .or_default()
+            .extend(translations);
+    }
+
+    /// Set the locales tried after the current locale's base languages.
+    /// Defaults to the locale given to `new` when empty.
+    pub fn set_fallback(&mut self, locales: &[&str]) {
+        self.fallback = locales.iter().map(|l| l.to_string()).collect();
+    }
+
+    /// Text direction of the current locale
+    pub fn text_direction(&self) -> TextDirection {
+        Locale::from_str(&self.current_locale)
+            .map(|l| l.text_direction())
+            .unwrap_or_default()
+    }
+
+    /// Locales tried in order when looking up a key for `locale`,
+    /// e.g. `ja-JP` -> `ja` -> fallback locales
+    pub fn fallback_chain(&self, locale: &str) -> Vec<String> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut candidate = locale;
+        loop {
+            chain.push(candidate.to_string());
+            match candidate.rfind(['-', '_']) {
+                Some(pos) => candidate = &candidate[..pos],
+                None => break,
+            }
+        }
+
+        let tail = if self.fallback.is_empty() {
+            std::slice::from_ref(self.default_locale())
+        } else {
+            self.fallback.as_slice()
+        };
+        for locale in tail {
+            if !chain.contains(locale) {
+                chain.push(locale.clone());
+            }
+        }
+        chain
+    }
+
+    /// Set current locale. Succeeds when the locale or one of its fallbacks is loaded.
     pub fn set_locale(&mut self, locale: &str) -> Result<()> {
-        if !self.locales.contains_key(locale) {
+        if !self.fallback_chain(locale).iter().any(|l| self.locales.contains_key(l)) {
             return Err(anyhow!("Locale '{}' not loaded", locale));
         }
         self.current_locale = locale.to_string();
         Ok(())
     }
 
-    /// Get translation for key
+    /// Run `f` with `locale` as the current locale, restoring the previous
+    /// locale afterwards, even if `f` panics.
+    ///
+    /// The exclusive borrow keeps the switch invisible to other users of this
+    /// instance; to share one across threads, wrap it in a `Mutex` and hold the
+    /// lock for the whole call.
+    pub fn with_locale<T>(&mut self, locale: &str, f: impl FnOnce(&Self) -> T) -> Result<T> {
+        let previous = self.current_locale.clone();
+        self.set_locale(locale)?;
+
+        let guard = LocaleGuard { i18n: self, previous };
+        Ok(f(guard.i18n))
+    }
+
+    /// In strict mode, `translate` logs a warning the first time a key
+    /// is missing from the current locale, even if a fallback resolves it
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Keys present in the default locale but absent in the given locale, sorted
+    pub fn missing_keys(&self, locale: &str) -> Vec<String> {
+        let Some(default_map) = self.locales.get(self.default_locale()) else {
+            return Vec::new();
+        };
+        let locale_map = self.locales.get(locale);
+
+        let mut missing: Vec<String> = default_map.keys()
+            .filter(|k| !locale_map.is_some_and(|m| m.contains_key(*k)))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing
+    }
+
+    /// Get translation for key, walking the fallback chain of the current locale
     pub fn translate(&self, key: &str) -> Result<&str> {
-        let locale_map = self.locales.get(&self.current_locale)
-            .ok_or_else(|| anyhow!("Current locale '{}' not loaded", self.current_locale))?;
-            
-        locale_map.get(key)
+        if self.strict {
+            self.report_missing(key);
+        }
+        self.lookup(key)
+    }
+
+    fn lookup(&self, key: &str) -> Result<&str> {
+        let locale_maps: Vec<&LocaleMap> = self.fallback_chain(&self.current_locale)
+            .iter()
+            .filter_map(|l| self.locales.get(l))
+            .collect();
+        if locale_maps.is_empty() {
+            return Err(anyhow!("Current locale '{}' not loaded", self.current_locale));
+        }
+
+        locale_maps.iter()
+            .find_map(|m| m.get(key))
             .map(|s| s.as_str())
             .ok_or_else(|| anyhow!("Translation key '{}' not found", key))
     }
     
+    fn default_locale(&self) -> &String {
+        self.default_locale.as_ref().unwrap_or(&self.current_locale)
+    }
+
+    /// Missing keys reported in strict mode so far, as `locale:key`, sorted
+    pub fn reported_missing(&self) -> Vec<String> {
+        let reported = self.reported_missing.lock().unwrap_or_else(|e| e.into_inner());
+        let mut keys: Vec<String> = reported.iter().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Warn about a key missing from the current locale; returns whether a
+    /// warning was logged, which happens once per locale and key
+    fn report_missing(&self, key: &str) -> bool {
+        let in_current = self.locales.get(&self.current_locale)
+            .is_some_and(|m| m.contains_key(key));
+        if in_current {
+            return false;
+        }
+
+        let mut reported = self.reported_missing.lock().unwrap_or_else(|e| e.into_inner());
+        let first = reported.insert(format!("{}:{}", self.current_locale, key));
+        if first {
+            warn!("Translation key '{}' missing in locale '{}'", key, self.current_locale);
+        }
+        first
+    }
+
     /// Shorthand for translate
     pub fn t(&self, key: &str) -> String {
         self.translate(key).unwrap_or(key).to_string()
     }
+
+    /// Translate and replace `{name}` placeholders with the given values.
+    /// `{num:name}` formats a numeric value with `format_number`; other values
+    /// are inserted as is.
+    pub fn tf(&self, key: &str, vars: &[(&str, &str)]) -> String {
+        self.substitute(&self.t(key), vars)
+    }
+
+    /// Translate a plural message for `count`, substituting `{count}` and `vars`.
+    ///
+    /// Plural forms are stored as sibling keys suffixed with the CLDR category,
+    /// e.g. `"files_copied.one"` and `"files_copied.other"`. An optional
+    /// `.zero` entry takes precedence when `count` is 0, and `.other` is used
+    /// when the locale's category has no entry.
+    pub fn tp(&self, key: &str, count: u64, vars: &[(&str, &str)]) -> String {
+        let category = plural_category(&self.current_locale, count);
+        let mut candidates = Vec::new();
+        if count == 0 {
+            candidates.push(format!("{}.zero", key));
+        }
+        candidates.push(format!("{}.{}", key, category));
+        candidates.push(format!("{}.other", key));
+
+        // Only the candidate that resolves is reported in strict mode; a missing
+        // `.zero` is optional and not worth a warning
+        let resolved = candidates.iter()
+            .find_map(|k| self.lookup(k).ok().map(|message| (k, message)));
+        if self.strict {
+            let fallback = format!("{}.{}", key, category);
+            self.report_missing(resolved.map_or(&fallback, |(k, _)| k));
+        }
+        let message = resolved.map_or(key, |(_, message)| message);
+
+        let count = count.to_string();
+        let mut all_vars = vec![("count", count.as_str())];
+        all_vars.extend_from_slice(vars);
+        self.substitute(message, &all_vars)
+    }
+
+    /// Format a number for the current locale, e.g. `1,234.5` in `en`
+    pub fn format_number(&self, n: f64) -> String {
+        let plain = n.to_string();
+        if !n.is_finite() || language(&self.current_locale) == "ja" {
+            return plain;
+        }
+
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", plain.as_str()),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((i, f)) => (i, Some(f)),
+            None => (digits, None),
+        };
+
+        let mut grouped = String::new();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+
+        match fraction {
+            Some(f) => format!("{}{}.{}", sign, grouped, f),
+            None => format!("{}{}", sign, grouped),
+        }
+    }
+
+    /// Format a date for the current locale, e.g. `03/14/2024` in `en`
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let pattern = match language(&self.current_locale) {
+            "en" => "%m/%d/%Y",
+            "ja" => "%Y年%m月%d日",
+            _ => "%Y-%m-%d",
+        };
+        date.format(pattern).to_string()
+    }
+
+    fn substitute(&self, message: &str, vars: &[(&str, &str)]) -> String {
+        vars.iter().fold(message.to_string(), |acc, (name, value)| {
+            // Values that are not numbers are substituted as is
+            let number = value.parse::<f64>()
+                .map(|n| self.format_number(n))
+                .unwrap_or_else(|_| value.to_string());
+            acc.replace(&format!("{{num:{}}}", name), &number)
+                .replace(&format!("{{{}}}", name), value)
+        })
+    }
+}
+
+/// Restores the previous locale when a `with_locale` scope ends
+struct LocaleGuard<'a> {
+    i18n: &'a mut I18n,
+    previous: String,
+}
+
+impl Drop for LocaleGuard<'_> {
+    fn drop(&mut self) {
+        self.i18n.current_locale = std::mem::take(&mut self.previous);
+    }
+}
+
+/// CLDR plural category of `count` for the language of `locale`
+pub fn plural_category(locale: &str, count: u64) -> &'static str {
+    match language(locale) {
+        "ja" | "zh" | "ko" => "other",
+        _ => if count == 1 { "one" } else { "other" },
+    }
+}
+
+fn collect_files(dir: &Path, names: &[String], files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        // symlink_metadata so a link back to a parent directory cannot recurse forever
+        if std::fs::symlink_metadata(&path)?.is_dir() {
+            collect_files(&path, names, files)?;
+        } else if path.is_file() && path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| names.iter().any(|name| name == n))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
 }
 
 pub fn add(left: u64, right: u64) -> u64 {
@@ -72,4 +501,202 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn falls_back_to_default_locale() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("greeting".to_string(), "Hello".to_string()),
+        ]));
+
+        i18n.set_locale("ja").unwrap();
+        assert_eq!(i18n.t("greeting"), "Hello");
+        assert_eq!(i18n.t("missing"), "missing");
+    }
+
+    #[test]
+    fn falls_back_to_base_language() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("greeting".to_string(), "Hello".to_string()),
+        ]));
+        i18n.add_translations("ja", HashMap::from([
+            ("greeting".to_string(), "こんにちは".to_string()),
+        ]));
+
+        i18n.set_locale("ja-JP").unwrap();
+        assert_eq!(i18n.t("greeting"), "こんにちは");
+        assert_eq!(i18n.fallback_chain("ja-JP"), vec!["ja-JP", "ja", "en"]);
+
+        i18n.set_fallback(&["fr", "en"]);
+        assert_eq!(i18n.fallback_chain("ja-JP"), vec!["ja-JP", "ja", "fr", "en"]);
+    }
+
+    #[test]
+    fn deserializes_without_default_locale() {
+        let i18n: I18n = serde_json::from_str(
+            r#"{"locales": {"en": {"app": "Dougu"}}, "current_locale": "en"}"#,
+        ).unwrap();
+        assert_eq!(i18n.fallback_chain("ja"), vec!["ja", "en"]);
+        assert_eq!(i18n.missing_keys("ja"), vec!["app"]);
+    }
+
+    #[test]
+    fn reports_missing_keys() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("copied".to_string(), "Copied".to_string()),
+            ("moved".to_string(), "Moved".to_string()),
+        ]));
+        i18n.add_translations("ja", HashMap::from([
+            ("copied".to_string(), "コピーしました".to_string()),
+        ]));
+
+        assert_eq!(i18n.missing_keys("ja"), vec!["moved"]);
+        assert_eq!(i18n.missing_keys("en"), Vec::<String>::new());
+
+        i18n.set_locale("ja").unwrap();
+        assert_eq!(i18n.t("moved"), "Moved");
+        assert!(i18n.reported_missing().is_empty());
+
+        i18n.set_strict(true);
+        assert_eq!(i18n.t("moved"), "Moved");
+        assert_eq!(i18n.t("copied"), "コピーしました");
+        assert_eq!(i18n.reported_missing(), vec!["ja:moved"]);
+        assert!(!i18n.report_missing("moved"));
+
+        i18n.add_translations("en", HashMap::from([
+            ("files.one".to_string(), "{count} file".to_string()),
+            ("files.other".to_string(), "{count} files".to_string()),
+        ]));
+        assert_eq!(i18n.tp("files", 2, &[]), "2 files");
+        assert_eq!(i18n.reported_missing(), vec!["ja:files.other", "ja:moved"]);
+    }
+
+    #[test]
+    fn selects_plural_forms() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("copied.zero".to_string(), "No files copied to {dest}".to_string()),
+            ("copied.one".to_string(), "{count} file copied to {dest}".to_string()),
+            ("copied.other".to_string(), "{count} files copied to {dest}".to_string()),
+        ]));
+
+        let vars = [("dest", "/tmp")];
+        assert_eq!(i18n.tp("copied", 0, &vars), "No files copied to /tmp");
+        assert_eq!(i18n.tp("copied", 1, &vars), "1 file copied to /tmp");
+        assert_eq!(i18n.tp("copied", 5, &vars), "5 files copied to /tmp");
+        assert_eq!(plural_category("ja", 1), "other");
+    }
+
+    #[test]
+    fn loads_translations_from_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("i18n-en.json"), r#"{"app":"Dougu"}"#).unwrap();
+        std::fs::write(
+            dir.path().join("sub").join("en.json"),
+            r#"{"copied":"Copied","app":"Other"}"#,
+        ).unwrap();
+        std::fs::write(dir.path().join("i18n-ja.json"), r#"{"app":"道具"}"#).unwrap();
+
+        let mut i18n = I18n::new("en");
+        i18n.load_dir("en", dir.path()).unwrap();
+        assert_eq!(i18n.t("app"), "Dougu");
+        assert_eq!(i18n.t("copied"), "Copied");
+
+        assert!(i18n.load_dir("fr", dir.path()).is_err());
+
+        std::fs::write(dir.path().join("fr.json"), r#"{"app":"Outil"}"#).unwrap();
+        std::fs::write(dir.path().join("sub").join("fr.json"), "{").unwrap();
+        assert!(i18n.load_dir("fr", dir.path()).is_err());
+        assert_eq!(i18n.missing_keys("fr"), vec!["app", "copied"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_dir_skips_symlinked_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("en.json"), r#"{"app":"Dougu"}"#).unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let mut i18n = I18n::new("en");
+        i18n.load_dir("en", dir.path()).unwrap();
+        assert_eq!(i18n.t("app"), "Dougu");
+    }
+
+    #[test]
+    fn formats_numbers_and_dates_per_locale() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("total".to_string(), "Total: {num:bytes} bytes".to_string()),
+        ]));
+        i18n.add_translations("ja", HashMap::new());
+        let date = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+
+        assert_eq!(i18n.format_number(1234.5), "1,234.5");
+        assert_eq!(i18n.format_number(-1234567.0), "-1,234,567");
+        assert_eq!(i18n.format_date(date), "03/14/2024");
+        assert_eq!(i18n.tf("total", &[("bytes", "1234567")]), "Total: 1,234,567 bytes");
+
+        i18n.set_locale("ja").unwrap();
+        assert_eq!(i18n.format_number(1234.5), "1234.5");
+        assert_eq!(i18n.format_date(date), "2024年03月14日");
+    }
+
+    #[test]
+    fn num_placeholder_keeps_non_numeric_values() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("total".to_string(), "Total: {num:bytes} bytes".to_string()),
+        ]));
+
+        assert_eq!(i18n.tf("total", &[("bytes", "unknown")]), "Total: unknown bytes");
+    }
+
+    #[test]
+    fn detects_rtl_locales() {
+        assert!(Locale::from_str("ar").unwrap().is_rtl());
+        assert!(Locale::from_str("he_IL.UTF-8").unwrap().is_rtl());
+        assert!(!Locale::from_str("en").unwrap().is_rtl());
+        assert_eq!(Locale::from_str("ja_JP.UTF-8").unwrap().to_string(), "ja-JP");
+        assert!(Locale::from_str("").is_err());
+
+        let locale = Locale::from_str("zh-Hant-TW").unwrap();
+        assert_eq!(locale.script(), Some("Hant"));
+        assert_eq!(locale.region(), Some("TW"));
+        assert_eq!(locale.to_string(), "zh-Hant-TW");
+        let locale = Locale::from_str("sr_latn_RS").unwrap();
+        assert_eq!(locale.to_string(), "sr-Latn-RS");
+        assert_eq!(Locale::from_str("zh-Hans").unwrap().region(), None);
+
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("ar", HashMap::new());
+        assert_eq!(i18n.text_direction(), TextDirection::LeftToRight);
+        i18n.set_locale("ar").unwrap();
+        assert_eq!(i18n.text_direction(), TextDirection::RightToLeft);
+    }
+
+    #[test]
+    fn resolves_auto_locale() {
+        let detected = || Some("ja_JP.UTF-8".to_string());
+        assert_eq!(resolve_locale_with("auto", "en", detected), "ja");
+        assert_eq!(resolve_locale_with("en", "en", detected), "en");
+        assert_eq!(resolve_locale_with("auto", "en", || None), "en");
+    }
+
+    #[test]
+    fn switches_locale_within_scope() {
+        let mut i18n = I18n::new("en");
+        i18n.add_translations("en", HashMap::from([
+            ("greeting".to_string(), "Hello".to_string()),
+        ]));
+        i18n.add_translations("ja", HashMap::from([
+            ("greeting".to_string(), "こんにちは".to_string()),
+        ]));
+
+        let inner = i18n.with_locale("ja", |i18n| i18n.t("greeting")).unwrap();
+        assert_eq!(inner, "こんにちは");
+        assert_eq!(i18n.t("greeting"), "Hello");
+    }
 }
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Defaults for global options, loaded from a TOML file.
+/// Options given on the command line take precedence.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Verbosity level (0-5)
+    pub verbose: Option<u8>,
+
+    /// Keys this version does not understand
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+impl Config {
+    /// Default location: `<config dir>/dougu/config.toml` (`$XDG_CONFIG_HOME` on Linux)
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("dougu").join("config.toml"))
+    }
+
+    /// Load the given file, or the default location if it exists.
+    /// An explicitly given file must exist.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::load_file(path),
+            None => match Self::default_path().filter(|p| p.exists()) {
+                Some(path) => Self::load_file(&path),
+                None => Ok(Self::default()),
+            },
+        }
+    }
+
+    fn load_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read config file '{}'", path.display()))?;
+        Self::parse(&content)
+            .with_context(|| format!("Invalid config file '{}'", path.display()))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Verbosity from the command line, else from the file, else 2
+    pub fn verbose_or(&self, cli: Option<u8>) -> u8 {
+        cli.or(self.verbose).unwrap_or(2)
+    }
+
+    /// Keys present in the file that are not recognized
+    pub fn unknown_keys(&self) -> impl Iterator<Item = &str> {
+        self.unknown.keys().map(|k| k.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_and_unknown_keys() {
+        let config = Config::parse("verbose = 4\ncolour = \"blue\"\n").unwrap();
+        assert_eq!(config.verbose, Some(4));
+        assert_eq!(config.unknown_keys().collect::<Vec<_>>(), vec!["colour"]);
+    }
+
+    #[test]
+    fn command_line_overrides_config() {
+        let config = Config::parse("verbose = 4\n").unwrap();
+        assert_eq!(config.verbose_or(Some(1)), 1);
+        assert_eq!(config.verbose_or(None), 4);
+
+        let config = Config::default();
+        assert_eq!(config.verbose_or(Some(1)), 1);
+        assert_eq!(config.verbose_or(None), 2);
+    }
+
+    #[test]
+    fn explicit_file_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Config::load(Some(&dir.path().join("missing.toml"))).is_err());
+
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "verbose = 0\n").unwrap();
+        assert_eq!(Config::load(Some(&path)).unwrap().verbose, Some(0));
+    }
+}
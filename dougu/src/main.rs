@@ -1,6 +1,11 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use log::LevelFilter;
+use std::path::PathBuf;
+
+mod config;
+
+use config::Config;
 
 use dougu_command_file::{FileArgs, FileCommands};
 use dougu_command_dropbox::{DropboxArgs, DropboxCommands, FileCommands as DropboxFileCommands};
@@ -8,9 +13,17 @@ use dougu_command_dropbox::{DropboxArgs, DropboxCommands, FileCommands as Dropbo
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Set verbosity level (0-5)
-    #[arg(short, long, default_value_t = 2)]
-    verbose: u8,
+    /// Set verbosity level (0-5) [default: 2]
+    #[arg(short, long)]
+    verbose: Option<u8>,
+
+    /// Config file with defaults for these options [default: <config dir>/dougu/config.toml]
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Show what mutating commands would do without changing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
 
     #[command(subcommand)]
     command: Commands,
@@ -28,9 +41,10 @@ enum Commands {
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref())?;
     
     // Set up logging based on verbosity
-    let level = match cli.verbose {
+    let level = match config.verbose_or(cli.verbose) {
         0 => LevelFilter::Off,
         1 => LevelFilter::Error,
         2 => LevelFilter::Warn,
@@ -41,27 +55,39 @@ async fn main() -> Result<()> {
     
     dougu_essentials_logger::init(level)?;
     
+    for key in config.unknown_keys() {
+        dougu_essentials_logger::log_warning(format!("Unknown config key '{}' ignored", key));
+    }
+    
     // Process commands
     match &cli.command {
         Commands::File(args) => {
             match &args.command {
                 FileCommands::Copy(copy_args) => {
-                    dougu_command_file::execute_copy(copy_args)?;
+                    let result = dougu_command_file::execute_copy(copy_args, cli.dry_run)?;
+                    println!("{}", result.message);
                 }
                 FileCommands::Move(move_args) => {
-                    dougu_command_file::execute_move(move_args)?;
+                    let result = dougu_command_file::execute_move(move_args, cli.dry_run)?;
+                    println!("{}", result.message);
                 }
                 FileCommands::List(list_args) => {
-                    dougu_command_file::execute_list(list_args)?;
+                    let result = dougu_command_file::execute_list(list_args)?;
+                    if list_args.json {
+                        println!("{}", result.to_json()?);
+                    } else if !result.entries.is_empty() {
+                        println!("{}", result.render(list_args.long));
+                    }
                 }
             }
         }
         Commands::Dropbox(args) => {
-            // For demo purposes, use a dummy token
-            let token = "dummy_dropbox_token";
-            
             match &args.command {
+                DropboxCommands::Auth(auth_args) => {
+                    dougu_command_dropbox::execute_auth(auth_args, cli.dry_run).await?;
+                }
                 DropboxCommands::File(file_args) => {
+                    let token = &args.resolve_token().await?;
                     match &file_args.command {
                         DropboxFileCommands::List(list_args) => {
                             dougu_command_dropbox::execute_file_list(list_args, token).await?;
@@ -72,9 +98,13 @@ async fn main() -> Result<()> {
                         DropboxFileCommands::Upload(upload_args) => {
                             dougu_command_dropbox::execute_file_upload(upload_args, token).await?;
                         }
+                        DropboxFileCommands::Share(share_args) => {
+                            dougu_command_dropbox::execute_file_share(share_args, token, cli.dry_run).await?;
+                        }
                     }
                 }
                 DropboxCommands::Folder(folder_args) => {
+                    let token = &args.resolve_token().await?;
                     match &folder_args.command {
                         dougu_command_dropbox::FolderCommands::Create(create_args) => {
                             dougu_command_dropbox::execute_folder_create(create_args, token).await?;